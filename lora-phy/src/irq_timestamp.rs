@@ -4,16 +4,99 @@
 //! requests (IRQs) are received from the radio hardware. This is useful for precise
 //! timing measurements and diagnostics.
 
-use core::sync::atomic::Ordering;
+#[cfg(feature = "irq-history")]
+use core::sync::atomic::{AtomicU32, AtomicUsize};
+use core::sync::atomic::{AtomicU8, AtomicU16, Ordering};
 
 #[cfg(target_has_atomic = "64")]
 use core::sync::atomic::AtomicU64;
 #[cfg(not(target_has_atomic = "64"))]
 use portable_atomic::AtomicU64;
 
+/// The kinds of radio IRQ events that can carry an individual timestamp.
+///
+/// The discriminants correspond to the bit position of each event in the IRQ status
+/// word read back from the radio, so a status value can be decoded directly into the
+/// set of kinds that fired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum IrqKind {
+    TxDone = 0,
+    RxDone = 1,
+    PreambleDetected = 2,
+    SyncWordValid = 3,
+    HeaderValid = 4,
+    HeaderErr = 5,
+    CrcErr = 6,
+    CadDone = 7,
+    CadDetected = 8,
+    Timeout = 9,
+}
+
+/// All [`IrqKind`] variants, in discriminant order.
+const IRQ_KINDS: [IrqKind; IrqKind::COUNT] = [
+    IrqKind::TxDone,
+    IrqKind::RxDone,
+    IrqKind::PreambleDetected,
+    IrqKind::SyncWordValid,
+    IrqKind::HeaderValid,
+    IrqKind::HeaderErr,
+    IrqKind::CrcErr,
+    IrqKind::CadDone,
+    IrqKind::CadDetected,
+    IrqKind::Timeout,
+];
+
+impl IrqKind {
+    /// Number of distinct IRQ kinds tracked.
+    const COUNT: usize = 10;
+
+    /// The bit that represents this kind in an IRQ status word.
+    const fn mask(self) -> u16 {
+        1 << self as u16
+    }
+
+    /// Decodes a discriminant previously produced by `self as u8` back into an
+    /// [`IrqKind`]. Only used by the history ring, which stores kinds as raw bytes
+    /// in atomic slots; every byte it decodes was written by `push` from a valid
+    /// `IrqKind`, so an out-of-range byte indicates memory corruption.
+    #[cfg(feature = "irq-history")]
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => IrqKind::TxDone,
+            1 => IrqKind::RxDone,
+            2 => IrqKind::PreambleDetected,
+            3 => IrqKind::SyncWordValid,
+            4 => IrqKind::HeaderValid,
+            5 => IrqKind::HeaderErr,
+            6 => IrqKind::CrcErr,
+            7 => IrqKind::CadDone,
+            8 => IrqKind::CadDetected,
+            9 => IrqKind::Timeout,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Mask with every [`IrqKind`] bit set, the default arming state of an
+/// [`IrqTimestamps`].
+const ALL_IRQ_KINDS_MASK: u16 = {
+    let mut mask = 0;
+    let mut i = 0;
+    while i < IRQ_KINDS.len() {
+        mask |= IRQ_KINDS[i].mask();
+        i += 1;
+    }
+    mask
+};
+
 /// Stores the timestamp of the last recorded IRQ event in microseconds.
 static LAST_IRQ_TIMESTAMP_US: AtomicU64 = AtomicU64::new(0);
 
+/// Stores the timestamp of the last recorded IRQ event of each [`IrqKind`], in
+/// microseconds. A value of `0` means that kind has not fired yet.
+static LAST_IRQ_TIMESTAMP_US_BY_KIND: [AtomicU64; IrqKind::COUNT] = [const { AtomicU64::new(0) }; IrqKind::COUNT];
+
 /// Optional callback function to retrieve the current timestamp.
 static mut IRQ_TIMESTAMP_FN: Option<fn() -> u64> = None;
 
@@ -31,6 +114,7 @@ static mut IRQ_TIMESTAMP_FN: Option<fn() -> u64> = None;
 ///
 /// This function modifies a static mutable variable. The caller must ensure that
 /// this function is not called concurrently from multiple threads or interrupt contexts.
+#[deprecated(note = "store an `IrqTimestamps` in the radio instance instead of using process-wide statics")]
 pub fn set_irq_timestamp_fn(f: fn() -> u64) {
     unsafe {
         IRQ_TIMESTAMP_FN = Some(f);
@@ -46,13 +130,14 @@ pub fn set_irq_timestamp_fn(f: fn() -> u64) {
 ///
 /// This function modifies a static mutable variable. The caller must ensure that
 /// this function is not called concurrently from multiple threads or interrupt contexts.
+#[deprecated(note = "store an `IrqTimestamps` in the radio instance instead of using process-wide statics")]
 pub fn clear_irq_timestamp_fn() {
     unsafe {
         IRQ_TIMESTAMP_FN = None;
     }
 }
 
-/// Returns the timestamp of the last recorded IRQ event.
+/// Returns the timestamp of the last recorded IRQ event, regardless of kind.
 ///
 /// If no IRQ has been recorded yet, or if no timestamp function has been set
 /// via [`set_irq_timestamp_fn`], this will return 0.
@@ -60,18 +145,460 @@ pub fn clear_irq_timestamp_fn() {
 /// # Returns
 ///
 /// The last recorded IRQ timestamp in microseconds as a `u64` value
+#[deprecated(note = "store an `IrqTimestamps` in the radio instance instead of using process-wide statics")]
 pub fn last_irq_timestamp_us() -> u64 {
     LAST_IRQ_TIMESTAMP_US.load(Ordering::Relaxed)
 }
 
-/// Records the current timestamp when an IRQ status is read.
+/// Returns the timestamp of the last recorded IRQ event of the given `kind`.
+///
+/// Returns `None` if that kind has not fired yet, or if no timestamp function has
+/// been set via [`set_irq_timestamp_fn`].
+#[deprecated(note = "store an `IrqTimestamps` in the radio instance instead of using process-wide statics")]
+pub fn last_timestamp_for(kind: IrqKind) -> Option<u64> {
+    let timestamp = LAST_IRQ_TIMESTAMP_US_BY_KIND[kind as usize].load(Ordering::Relaxed);
+    if timestamp == 0 {
+        None
+    } else {
+        Some(timestamp)
+    }
+}
+
+/// Records the current timestamp for every IRQ kind set in `status`.
 ///
-/// This function is called internally by the radio driver when reading IRQ status.
-/// If a timestamp function has been set via [`set_irq_timestamp_fn`], it will be
-/// invoked and the result stored atomically for later retrieval via [`last_irq_timestamp_us`].
-pub(crate) fn record_irq_timestamp() {
+/// This function is called internally by the radio driver with the IRQ status word
+/// read from the hardware. If a timestamp function has been set via
+/// [`set_irq_timestamp_fn`], it is invoked once and the result is stored atomically
+/// into the overall last-timestamp slot as well as the slot of every [`IrqKind`] whose
+/// bit is set in `status`, for later retrieval via [`last_irq_timestamp_us`] and
+/// [`last_timestamp_for`].
+#[deprecated(note = "store an `IrqTimestamps` in the radio instance instead of using process-wide statics")]
+pub(crate) fn record_irq_timestamp(status: u16) {
     let f = unsafe { IRQ_TIMESTAMP_FN };
     if let Some(f) = f {
-        LAST_IRQ_TIMESTAMP_US.store(f(), Ordering::Relaxed);
+        let now = f();
+        LAST_IRQ_TIMESTAMP_US.store(now, Ordering::Relaxed);
+        for kind in IRQ_KINDS {
+            if status & kind.mask() != 0 {
+                LAST_IRQ_TIMESTAMP_US_BY_KIND[kind as usize].store(now, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Per-instance IRQ timestamp tracker.
+///
+/// Unlike the deprecated free functions above, which serialize every radio sharing
+/// the process through a handful of global statics, an `IrqTimestamps` is owned by
+/// a single radio driver/`RadioKind` instance, so two radios running in the same
+/// firmware (e.g. a dual-band node) each get their own collision-free timestamps.
+#[derive(Debug)]
+pub struct IrqTimestamps {
+    /// Set (and cleared) via `&mut self` before interrupts are enabled; every other
+    /// field below is read/written through `&self` so it is safe to take a shared
+    /// reference to this instance and call into it from the DIO pin ISR.
+    timestamp_fn: Option<fn() -> u64>,
+    last_timestamp_us: AtomicU64,
+    last_timestamp_us_by_kind: [AtomicU64; IrqKind::COUNT],
+    last_edge_timestamp_us: AtomicU64,
+    /// Bitmask of [`IrqKind`]s currently armed for timestamping. Kinds not in this
+    /// mask are skipped by [`record_irq_timestamp`](Self::record_irq_timestamp), which
+    /// also skips the clock callback invocation when no armed kind is set in the
+    /// status word, to keep the interrupt path lean.
+    /// An atomic so a ranging application can arm/disarm kinds from a different
+    /// context than the one calling `record_irq_timestamp`.
+    armed_mask: AtomicU16,
+    #[cfg(feature = "irq-timing-stats")]
+    timing: [TimingEstimator; IrqKind::COUNT],
+    #[cfg(feature = "irq-history")]
+    history: IrqHistory,
+}
+
+impl Default for IrqTimestamps {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IrqTimestamps {
+    /// Creates a tracker with no timestamp function set and every [`IrqKind`] armed.
+    pub const fn new() -> Self {
+        Self {
+            timestamp_fn: None,
+            last_timestamp_us: AtomicU64::new(0),
+            last_timestamp_us_by_kind: [const { AtomicU64::new(0) }; IrqKind::COUNT],
+            last_edge_timestamp_us: AtomicU64::new(0),
+            armed_mask: AtomicU16::new(ALL_IRQ_KINDS_MASK),
+            #[cfg(feature = "irq-timing-stats")]
+            timing: [const { TimingEstimator::new() }; IrqKind::COUNT],
+            #[cfg(feature = "irq-history")]
+            history: IrqHistory::new(),
+        }
+    }
+
+    /// Arms timestamping for `kind`, so future calls to
+    /// [`record_irq_timestamp`](Self::record_irq_timestamp) capture it again.
+    pub fn enable_timestamp(&self, kind: IrqKind) {
+        self.armed_mask.fetch_or(kind.mask(), Ordering::Relaxed);
+    }
+
+    /// Disarms timestamping for `kind`, so future calls to
+    /// [`record_irq_timestamp`](Self::record_irq_timestamp) skip it, including the
+    /// clock callback invocation if no other armed kind fired in the same status
+    /// word. All kinds are armed by default.
+    pub fn disable_timestamp(&self, kind: IrqKind) {
+        self.armed_mask.fetch_and(!kind.mask(), Ordering::Relaxed);
+    }
+
+    /// Returns whether `kind` is currently armed for timestamping.
+    pub fn is_timestamp_enabled(&self, kind: IrqKind) -> bool {
+        self.armed_mask.load(Ordering::Relaxed) & kind.mask() != 0
+    }
+
+    /// Sets the callback used to retrieve the current monotonic timestamp in
+    /// microseconds.
+    pub fn set_timestamp_fn(&mut self, f: fn() -> u64) {
+        self.timestamp_fn = Some(f);
+    }
+
+    /// Clears the timestamp callback. IRQ timestamps will no longer be recorded
+    /// until [`set_timestamp_fn`](Self::set_timestamp_fn) is called again.
+    pub fn clear_timestamp_fn(&mut self) {
+        self.timestamp_fn = None;
+    }
+
+    /// Returns the timestamp of the last recorded IRQ event, regardless of kind.
+    ///
+    /// Returns 0 if no IRQ has been recorded yet, or if no timestamp function has
+    /// been set.
+    pub fn last_irq_timestamp_us(&self) -> u64 {
+        self.last_timestamp_us.load(Ordering::Relaxed)
+    }
+
+    /// Returns the timestamp at which the IRQ status word containing the last
+    /// recorded event was read over the bus, i.e. [`last_irq_timestamp_us`](Self::last_irq_timestamp_us).
+    ///
+    /// This is an alias kept alongside [`last_irq_edge_us`](Self::last_irq_edge_us)
+    /// so call sites can make clear which of the two timestamps they mean.
+    pub fn last_irq_read_us(&self) -> u64 {
+        self.last_timestamp_us.load(Ordering::Relaxed)
+    }
+
+    /// Records the timestamp of a DIO pin edge, meant to be called directly from
+    /// the interrupt handler the instant the line rises, before the IRQ status is
+    /// read back over the bus. This avoids the SPI transaction latency and
+    /// scheduling jitter that [`record_irq_timestamp`](Self::record_irq_timestamp)
+    /// incurs, which matters for time-of-flight ranging.
+    ///
+    /// `timestamp_us` is the timestamp to record, not a callback, since the whole
+    /// point of this path is to avoid any indirection between the edge firing and
+    /// the timestamp being captured.
+    ///
+    /// Takes `&self`, not `&mut self`: it is callable from inside the DIO pin ISR
+    /// while other code may concurrently hold its own reference to the same
+    /// instance, so the timestamp is stored with a single atomic store rather than
+    /// through a borrow that would require exclusive access.
+    pub fn record_irq_edge(&self, timestamp_us: u64) {
+        self.last_edge_timestamp_us.store(timestamp_us, Ordering::Relaxed);
+    }
+
+    /// Returns the timestamp of the last recorded DIO pin edge, in microseconds, as
+    /// recorded via [`record_irq_edge`](Self::record_irq_edge).
+    ///
+    /// Returns 0 if no edge has been recorded yet.
+    pub fn last_irq_edge_us(&self) -> u64 {
+        self.last_edge_timestamp_us.load(Ordering::Relaxed)
+    }
+
+    /// Returns the delta, in microseconds, between the last recorded edge timestamp
+    /// and the last recorded read timestamp for the same event, i.e. the SPI/read
+    /// latency. The invariant is that the edge timestamp is always less than or
+    /// equal to the read timestamp for the same event, so this never underflows in
+    /// correct usage; it saturates to 0 otherwise.
+    pub fn irq_read_latency_us(&self) -> u64 {
+        self.last_irq_read_us().saturating_sub(self.last_irq_edge_us())
+    }
+
+    /// Returns the timestamp of the last recorded IRQ event of the given `kind`.
+    ///
+    /// Returns `None` if that kind has not fired yet, or if no timestamp function
+    /// has been set.
+    pub fn last_timestamp_for(&self, kind: IrqKind) -> Option<u64> {
+        let timestamp = self.last_timestamp_us_by_kind[kind as usize].load(Ordering::Relaxed);
+        if timestamp == 0 {
+            None
+        } else {
+            Some(timestamp)
+        }
+    }
+
+    /// Records the current timestamp for every IRQ kind set in `status`.
+    ///
+    /// This is called internally by the radio driver with the IRQ status word read
+    /// from the hardware. Only kinds currently armed (see
+    /// [`enable_timestamp`](Self::enable_timestamp)) are considered; if no armed kind
+    /// is set in `status`, nothing is recorded and the timestamp function is not
+    /// called. Otherwise, if a timestamp function has been set, it is invoked once
+    /// and the result is stored into the overall last-timestamp slot as well as the
+    /// slot of every armed [`IrqKind`] whose bit is set in `status`.
+    ///
+    /// Takes `&self`, backed entirely by atomic slots: a ranging application may
+    /// call [`enable_timestamp`](Self::enable_timestamp)/[`disable_timestamp`](Self::disable_timestamp)
+    /// from a different context (e.g. the task loop arming just `RxDone` before a
+    /// measurement) while this runs from the DIO ISR on the very next edge, so the
+    /// armed-kind mask needs to mean something even without exclusive access here.
+    pub(crate) fn record_irq_timestamp(&self, status: u16) {
+        let armed = status & self.armed_mask.load(Ordering::Relaxed);
+        if armed == 0 {
+            return;
+        }
+        let Some(f) = self.timestamp_fn else {
+            return;
+        };
+        let now = f();
+        self.last_timestamp_us.store(now, Ordering::Relaxed);
+        for kind in IRQ_KINDS {
+            if armed & kind.mask() != 0 {
+                let previous = self.last_timestamp_us_by_kind[kind as usize].swap(now, Ordering::Relaxed);
+                #[cfg(feature = "irq-timing-stats")]
+                if previous != 0 {
+                    self.timing[kind as usize].push(now.wrapping_sub(previous));
+                }
+                #[cfg(feature = "irq-history")]
+                self.history.push(kind, now);
+            }
+        }
+    }
+
+    /// Returns the inter-arrival timing statistics accumulated for `kind`, or `None`
+    /// if fewer than two events of that kind have been recorded yet.
+    #[cfg(feature = "irq-timing-stats")]
+    pub fn timing_stats(&self, kind: IrqKind) -> Option<TimingStats> {
+        self.timing[kind as usize].stats()
+    }
+
+    /// Predicts the timestamp, in microseconds, at which the next IRQ of `kind` is
+    /// likely to fire: the last observed timestamp plus the running mean
+    /// inter-arrival interval, plus the running jitter estimate so the prediction
+    /// errs toward "no later than" rather than the bare mean, which firmware can use
+    /// to size an MCU sleep window without waking up before the event is likely to
+    /// have fired. Returns `None` until at least two events of that kind have been
+    /// observed.
+    #[cfg(feature = "irq-timing-stats")]
+    pub fn predict_next_us(&self, kind: IrqKind) -> Option<u64> {
+        let last = self.last_timestamp_us_by_kind[kind as usize].load(Ordering::Relaxed);
+        let stats = self.timing_stats(kind)?;
+        Some(last.saturating_add(stats.mean_us).saturating_add(stats.jitter_us))
+    }
+
+    /// Drains the recorded IRQ event history, oldest first, as `(IrqKind, timestamp_us)`
+    /// pairs. Entries are removed from the history as they are yielded.
+    ///
+    /// Takes `&self`: the ring is written from [`record_irq_timestamp`](Self::record_irq_timestamp),
+    /// which may run from interrupt context while this is draining on the task
+    /// loop, so draining must not require exclusive access to `IrqTimestamps`.
+    #[cfg(feature = "irq-history")]
+    pub fn drain_irq_history(&self) -> IrqHistoryDrain<'_> {
+        IrqHistoryDrain { history: &self.history }
+    }
+
+    /// Returns the number of history entries dropped because they were overwritten
+    /// before being drained. The count is updated by the drain iterator when it
+    /// finds that entries were overwritten, so overwrites not yet seen by a drain are
+    /// not included. A nonzero count means [`drain_irq_history`](Self::drain_irq_history)
+    /// missed some events and there is a gap in the trace.
+    #[cfg(feature = "irq-history")]
+    pub fn dropped_irq_event_count(&self) -> u32 {
+        self.history.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Number of inter-arrival deltas retained per [`IrqKind`] for the running estimator.
+#[cfg(feature = "irq-timing-stats")]
+const TIMING_RING_LEN: usize = 16;
+
+/// Timing statistics for one [`IrqKind`], derived from its recent inter-arrival
+/// deltas.
+#[cfg(feature = "irq-timing-stats")]
+#[derive(Debug, Clone, Copy)]
+pub struct TimingStats {
+    /// Most recently observed inter-arrival interval, in microseconds.
+    pub last_us: u64,
+    /// Running mean inter-arrival interval, in microseconds.
+    pub mean_us: u64,
+    /// Running mean absolute deviation of the interval, used as a cheap jitter
+    /// estimate, in microseconds.
+    pub jitter_us: u64,
+    /// Smallest interval seen in the retained ring, in microseconds.
+    pub min_us: u64,
+    /// Largest interval seen in the retained ring, in microseconds.
+    pub max_us: u64,
+}
+
+/// Online estimator of the inter-arrival interval of one [`IrqKind`].
+///
+/// Keeps a short ring of recent deltas and a cheap mean/jitter EWMA
+/// (`mean += (delta - mean) >> EWMA_SHIFT`), so firmware can predict when the next
+/// event of a given kind is likely and schedule MCU sleep accordingly. All
+/// arithmetic is integer and uses wrapping ops so it stays overflow-safe on 32-bit
+/// targets. Every field is an atomic slot so `push` needs only `&self`, matching
+/// [`IrqTimestamps::record_irq_timestamp`], which owns the only writer.
+#[cfg(feature = "irq-timing-stats")]
+#[derive(Debug)]
+struct TimingEstimator {
+    ring: [AtomicU64; TIMING_RING_LEN],
+    filled: AtomicU8,
+    next: AtomicU8,
+    last_delta_us: AtomicU64,
+    mean_us: AtomicU64,
+    jitter_us: AtomicU64,
+}
+
+#[cfg(feature = "irq-timing-stats")]
+impl TimingEstimator {
+    /// Shift applied to the EWMA update; larger values weight history more heavily.
+    const EWMA_SHIFT: i64 = 3;
+
+    const fn new() -> Self {
+        Self {
+            ring: [const { AtomicU64::new(0) }; TIMING_RING_LEN],
+            filled: AtomicU8::new(0),
+            next: AtomicU8::new(0),
+            last_delta_us: AtomicU64::new(0),
+            mean_us: AtomicU64::new(0),
+            jitter_us: AtomicU64::new(0),
+        }
+    }
+
+    fn push(&self, delta_us: u64) {
+        self.last_delta_us.store(delta_us, Ordering::Relaxed);
+        let next = self.next.load(Ordering::Relaxed);
+        self.ring[next as usize].store(delta_us, Ordering::Relaxed);
+        self.next.store((next + 1) % TIMING_RING_LEN as u8, Ordering::Relaxed);
+
+        let filled = self.filled.load(Ordering::Relaxed);
+        if (filled as usize) < TIMING_RING_LEN {
+            self.filled.store(filled + 1, Ordering::Relaxed);
+        }
+
+        if filled == 0 {
+            self.mean_us.store(delta_us, Ordering::Relaxed);
+            self.jitter_us.store(0, Ordering::Relaxed);
+        } else {
+            let mean = self.mean_us.load(Ordering::Relaxed);
+            let diff = (delta_us as i64).wrapping_sub(mean as i64);
+            self.mean_us
+                .store((mean as i64).wrapping_add(diff >> Self::EWMA_SHIFT) as u64, Ordering::Relaxed);
+            let jitter = self.jitter_us.load(Ordering::Relaxed);
+            let jitter_diff = diff.wrapping_abs().wrapping_sub(jitter as i64);
+            self.jitter_us
+                .store((jitter as i64).wrapping_add(jitter_diff >> Self::EWMA_SHIFT) as u64, Ordering::Relaxed);
+        }
+    }
+
+    fn stats(&self) -> Option<TimingStats> {
+        let filled = self.filled.load(Ordering::Relaxed);
+        if filled < 1 {
+            return None;
+        }
+        let (mut min_us, mut max_us) = (u64::MAX, 0);
+        for slot in &self.ring[..filled as usize] {
+            let value = slot.load(Ordering::Relaxed);
+            min_us = min_us.min(value);
+            max_us = max_us.max(value);
+        }
+        Some(TimingStats {
+            last_us: self.last_delta_us.load(Ordering::Relaxed),
+            mean_us: self.mean_us.load(Ordering::Relaxed),
+            jitter_us: self.jitter_us.load(Ordering::Relaxed),
+            min_us,
+            max_us,
+        })
+    }
+}
+
+/// Capacity of the optional IRQ event history ring, in entries.
+#[cfg(feature = "irq-history")]
+const IRQ_HISTORY_LEN: usize = 64;
+
+/// Bounded ring buffer of `(IrqKind, timestamp_us)` pairs for the most recent IRQ
+/// events, for post-hoc diagnostics (e.g. replaying the ordering and spacing of
+/// interrupts around a missed RxDone or a spurious interrupt).
+///
+/// Every field is an atomic slot rather than a value behind a borrow, so a push from
+/// interrupt context is genuinely wait-free and can run concurrently with a drain on
+/// the task loop: the head/tail indices are monotonically increasing atomics, and
+/// each ring slot's kind/timestamp are themselves atomics rather than a plain tuple,
+/// so writing a slot never requires exclusive (`&mut`) access to the ring. When the
+/// ring is full, the oldest unread entry is overwritten; the next drain skips past
+/// the overwritten entries and adds their number to `dropped` so the consumer can
+/// detect the gap.
+#[cfg(feature = "irq-history")]
+#[derive(Debug)]
+struct IrqHistory {
+    kinds: [AtomicU8; IRQ_HISTORY_LEN],
+    timestamps_us: [AtomicU64; IRQ_HISTORY_LEN],
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    dropped: AtomicU32,
+}
+
+#[cfg(feature = "irq-history")]
+impl IrqHistory {
+    const fn new() -> Self {
+        Self {
+            kinds: [const { AtomicU8::new(0) }; IRQ_HISTORY_LEN],
+            timestamps_us: [const { AtomicU64::new(0) }; IRQ_HISTORY_LEN],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            dropped: AtomicU32::new(0),
+        }
+    }
+
+    /// Pushes a new entry. Takes `&self`: this is called from
+    /// [`IrqTimestamps::record_irq_timestamp`], which itself only ever needs `&self`
+    /// so it remains callable from interrupt context. `head` is the only index this
+    /// writes; `tail` is owned exclusively by [`IrqHistoryDrain`], so a push can never
+    /// race a drain over the same atomic.
+    fn push(&self, kind: IrqKind, timestamp_us: u64) {
+        let head = self.head.load(Ordering::Relaxed);
+        let slot = head % IRQ_HISTORY_LEN;
+        self.timestamps_us[slot].store(timestamp_us, Ordering::Relaxed);
+        self.kinds[slot].store(kind as u8, Ordering::Relaxed);
+        self.head.store(head.wrapping_add(1), Ordering::Relaxed);
+    }
+}
+
+/// Draining iterator over a history ring's unread `(IrqKind, timestamp_us)` entries,
+/// oldest first, returned by [`IrqTimestamps::drain_irq_history`].
+#[cfg(feature = "irq-history")]
+pub struct IrqHistoryDrain<'a> {
+    history: &'a IrqHistory,
+}
+
+#[cfg(feature = "irq-history")]
+impl Iterator for IrqHistoryDrain<'_> {
+    type Item = (IrqKind, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let head = self.history.head.load(Ordering::Relaxed);
+        let mut tail = self.history.tail.load(Ordering::Relaxed);
+        // Only the consumer moves `tail`, so it alone detects and records an overwrite:
+        // if the producer has lapped it, fast-forward past the entries that were lost
+        // and fold the gap into `dropped` here rather than racing the producer for it.
+        if head.wrapping_sub(tail) > IRQ_HISTORY_LEN {
+            let lost = head.wrapping_sub(tail) - IRQ_HISTORY_LEN;
+            self.history.dropped.fetch_add(lost as u32, Ordering::Relaxed);
+            tail = head.wrapping_sub(IRQ_HISTORY_LEN);
+            self.history.tail.store(tail, Ordering::Relaxed);
+        }
+        if tail == head {
+            return None;
+        }
+        let slot = tail % IRQ_HISTORY_LEN;
+        let kind = IrqKind::from_u8(self.history.kinds[slot].load(Ordering::Relaxed));
+        let timestamp_us = self.history.timestamps_us[slot].load(Ordering::Relaxed);
+        self.history.tail.store(tail.wrapping_add(1), Ordering::Relaxed);
+        Some((kind, timestamp_us))
     }
 }